@@ -1,10 +1,11 @@
 use serde::{Deserialize, Serialize};
-use std::fmt::{Display, Formatter};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
 
 use crate::deserializer::NbtReadHelper;
 use crate::serializer::WriteAdaptor;
 use crate::tag::NbtTag;
-use crate::{END_ID, Error, Nbt, get_nbt_string};
+use crate::{END_ID, Error, MAX_DEPTH, Nbt, get_nbt_string};
 use std::io::{ErrorKind, Read, Seek, Write};
 use std::vec::IntoIter;
 
@@ -14,7 +15,7 @@ use std::vec::IntoIter;
 /// which is often preferred in NBT serialization, though lookups are O(n).
 ///
 ///
-#[derive(Clone, Debug, Default, PartialEq, PartialOrd)]
+#[derive(Clone, Debug, Default)]
 pub struct NbtCompound {
     pub child_tags: Vec<(String, NbtTag)>,
 }
@@ -49,15 +50,33 @@ impl NbtCompound {
         Ok(())
     }
 
-    pub fn deserialize_content<R: Read + Seek>(
+    /// Reads the entries of a compound up to its End tag.
+    ///
+    /// Running out of input in place of the End tag is tolerated, which [`Nbt::read`] relies on
+    /// for its root compound. Nested compounds always need their End tag.
+    pub fn deserialize_content<R: Read>(reader: &mut NbtReadHelper<R>) -> Result<Self, Error> {
+        Self::deserialize_entries(reader, false, 0)
+    }
+
+    /// Reads the entries of a compound nested `depth` levels deep.
+    pub(crate) fn deserialize_entries<R: Read>(
         reader: &mut NbtReadHelper<R>,
+        require_end: bool,
+        depth: usize,
     ) -> Result<Self, Error> {
+        if depth >= MAX_DEPTH {
+            return Err(Error::TooDeep(MAX_DEPTH));
+        }
         let mut compound = Self::new();
 
         loop {
             let tag_id = match reader.get_u8_be() {
                 Ok(id) => id,
-                Err(Error::Incomplete(e)) if e.kind() == ErrorKind::UnexpectedEof => break,
+                Err(Error::Incomplete(e))
+                    if !require_end && e.kind() == ErrorKind::UnexpectedEof =>
+                {
+                    break;
+                }
                 Err(e) => return Err(e),
             };
 
@@ -66,7 +85,7 @@ impl NbtCompound {
             }
 
             let name = get_nbt_string(reader)?;
-            let tag = NbtTag::deserialize_data(reader, tag_id)?;
+            let tag = NbtTag::deserialize_nested(reader, tag_id, depth + 1)?;
 
             compound.child_tags.push((name, tag));
         }
@@ -74,10 +93,10 @@ impl NbtCompound {
         Ok(compound)
     }
 
-    pub fn serialize_content<W: Write>(self, w: &mut WriteAdaptor<W>) -> Result<(), Error> {
-        for (name, tag) in self.child_tags {
+    pub fn serialize_content<W: Write>(&self, w: &mut WriteAdaptor<W>) -> Result<(), Error> {
+        for (name, tag) in &self.child_tags {
             w.write_u8_be(tag.get_type_id())?;
-            NbtTag::write_string(&name, w)?;
+            NbtTag::write_string(name, w)?;
             tag.serialize_data(w)?;
         }
         w.write_u8_be(END_ID)?;
@@ -134,6 +153,23 @@ impl NbtCompound {
         self.put(name, NbtTag::Compound(value));
     }
 
+    /// Deep-merges `other` into this compound.
+    ///
+    /// Keys holding a compound on both sides are merged recursively, any other value in `other`
+    /// replaces the existing one in place. Keys missing from `self` are appended at the end.
+    pub fn merge(&mut self, other: Self) {
+        for (key, value) in other.child_tags {
+            let existing = self.child_tags.iter_mut().find(|(name, _)| *name == key);
+            match (existing, value) {
+                (Some((_, NbtTag::Compound(existing))), NbtTag::Compound(value)) => {
+                    existing.merge(value);
+                }
+                (Some((_, existing)), value) => *existing = value,
+                (None, value) => self.child_tags.push((key, value)),
+            }
+        }
+    }
+
     #[must_use]
     pub fn get_byte(&self, name: &str) -> Option<i8> {
         self.get(name).and_then(super::tag::NbtTag::extract_byte)
@@ -202,6 +238,53 @@ impl NbtCompound {
     pub fn get_long_array(&self, name: &str) -> Option<&[i64]> {
         self.get(name).and_then(|tag| tag.extract_long_array())
     }
+
+    /// Compares two compounds by their content, ignoring the order of their keys.
+    #[must_use]
+    pub fn content_eq(&self, other: &Self) -> bool {
+        let (entries, other_entries) = (self.sorted_entries(), other.sorted_entries());
+        entries.len() == other_entries.len()
+            && entries
+                .iter()
+                .zip(&other_entries)
+                .all(|((key, tag), (other_key, other_tag))| {
+                    key == other_key && tag.content_eq(other_tag)
+                })
+    }
+
+    /// Returns the entries sorted by key, keeping only the first entry of a duplicated key
+    /// like [`Self::get`] does. Equality, hashing and ordering all work on this view.
+    fn sorted_entries(&self) -> Vec<(&str, &NbtTag)> {
+        let mut entries: Vec<_> = self
+            .child_tags
+            .iter()
+            .map(|(key, tag)| (key.as_str(), tag))
+            .collect();
+        // The sort is stable, so the first entry of each key is the one kept
+        entries.sort_by_key(|(key, _)| *key);
+        entries.dedup_by_key(|(key, _)| *key);
+        entries
+    }
+}
+
+impl PartialEq for NbtCompound {
+    fn eq(&self, other: &Self) -> bool {
+        self.content_eq(other)
+    }
+}
+
+impl Eq for NbtCompound {}
+
+impl Hash for NbtCompound {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.sorted_entries().hash(state);
+    }
+}
+
+impl PartialOrd for NbtCompound {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.sorted_entries().partial_cmp(&other.sorted_entries())
+    }
 }
 
 impl From<Nbt> for NbtCompound {
@@ -286,72 +369,119 @@ impl From<NbtCompound> for NbtTag {
     }
 }
 
-/// SNBT display implementation for `NbtCompound`
-impl Display for NbtCompound {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str("{")?;
-        for (i, (key, value)) in self.child_tags.iter().enumerate() {
-            if i > 0 {
-                f.write_str(", ")?;
-            }
-            write!(f, "{key}: {value}")?;
-        }
-        f.write_str("}")
-    }
-}
+#[cfg(test)]
+mod test {
+    use std::cmp::Ordering;
+    use std::hash::{BuildHasher, RandomState};
 
-impl Display for NbtTag {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::End => Ok(()),
-            Self::Byte(v) => write!(f, "{v}b"),
-            Self::Short(v) => write!(f, "{v}s"),
-            Self::Int(v) => write!(f, "{v}"),
-            Self::Long(v) => write!(f, "{v}L"),
-            Self::Float(v) => write!(f, "{v}f"),
-            Self::Double(v) => write!(f, "{v}d"),
-            Self::String(v) => write!(f, "\"{v}\""), // TODO: Proper escaping needed for robust SNBT
-            Self::Compound(v) => write!(f, "{v}"),
-            Self::ByteArray(v) => {
-                f.write_str("[B;")?;
-                for (i, byte) in v.iter().enumerate() {
-                    if i > 0 {
-                        f.write_str(",")?;
-                    }
-                    write!(f, " {byte}b")?;
-                }
-                f.write_str("]")
-            }
-            Self::List(v) => {
-                f.write_str("[")?;
-                for (i, tag) in v.iter().enumerate() {
-                    if i > 0 {
-                        f.write_str(", ")?;
-                    }
-                    write!(f, "{tag}")?;
-                }
-                f.write_str("]")
-            }
-            Self::IntArray(v) => {
-                f.write_str("[I;")?;
-                for (i, int) in v.iter().enumerate() {
-                    if i > 0 {
-                        f.write_str(",")?;
-                    }
-                    write!(f, " {int}")?;
-                }
-                f.write_str("]")
-            }
-            Self::LongArray(v) => {
-                f.write_str("[L;")?;
-                for (i, long) in v.iter().enumerate() {
-                    if i > 0 {
-                        f.write_str(",")?;
-                    }
-                    write!(f, " {long}L")?;
-                }
-                f.write_str("]")
-            }
-        }
+    use crate::compound::NbtCompound;
+    use crate::tag::NbtTag;
+
+    fn keys(compound: &NbtCompound) -> Vec<&str> {
+        compound
+            .child_tags
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn merge_nested() {
+        let mut base_inner = NbtCompound::new();
+        base_inner.put_int("x", 1);
+        base_inner.put_int("y", 2);
+        let mut base = NbtCompound::new();
+        base.put_component("inner", base_inner);
+        base.put_string("name", "base".to_string());
+
+        let mut override_inner = NbtCompound::new();
+        override_inner.put_int("z", 3);
+        override_inner.put_int("x", 10);
+        let mut overrides = NbtCompound::new();
+        overrides.put_string("extra", "new".to_string());
+        overrides.put_component("inner", override_inner);
+
+        base.merge(overrides);
+
+        assert_eq!(keys(&base), ["inner", "name", "extra"]);
+        let inner = base.get_compound("inner").unwrap();
+        assert_eq!(keys(inner), ["x", "y", "z"]);
+        assert_eq!(inner.get_int("x"), Some(10));
+        assert_eq!(inner.get_int("y"), Some(2));
+        assert_eq!(inner.get_int("z"), Some(3));
+        assert_eq!(base.get_string("extra"), Some("new"));
+    }
+
+    #[test]
+    fn merge_scalar_over_compound() {
+        let mut base = NbtCompound::new();
+        base.put_component("a", NbtCompound::new());
+        base.put_int("b", 1);
+
+        let mut overrides = NbtCompound::new();
+        overrides.put_int("a", 5);
+        base.merge(overrides);
+
+        assert_eq!(keys(&base), ["a", "b"]);
+        assert_eq!(base.get("a"), Some(&NbtTag::Int(5)));
+    }
+
+    #[test]
+    fn merge_compound_over_scalar() {
+        let mut base = NbtCompound::new();
+        base.put_int("a", 1);
+        base.put_int("b", 2);
+
+        let mut inner = NbtCompound::new();
+        inner.put_bool("flag", true);
+        let mut overrides = NbtCompound::new();
+        overrides.put_component("a", inner.clone());
+        base.merge(overrides);
+
+        assert_eq!(keys(&base), ["a", "b"]);
+        assert_eq!(base.get_compound("a"), Some(&inner));
+    }
+
+    #[test]
+    fn duplicate_keys_compare_symmetrically() {
+        let mut duplicated = NbtCompound::new();
+        duplicated
+            .child_tags
+            .push(("a".to_string(), NbtTag::Int(1)));
+        duplicated
+            .child_tags
+            .push(("a".to_string(), NbtTag::Int(1)));
+        let mut other = NbtCompound::new();
+        other.put_int("a", 1);
+        other.put_int("b", 2);
+
+        assert_ne!(duplicated, other);
+        assert_ne!(other, duplicated);
+
+        // Only the first entry of a duplicated key counts, just like `get`
+        let mut single = NbtCompound::new();
+        single.put_int("a", 1);
+        assert_eq!(duplicated, single);
+        assert_eq!(single, duplicated);
+        let state = RandomState::new();
+        assert_eq!(state.hash_one(&duplicated), state.hash_one(&single));
+    }
+
+    #[test]
+    fn ordering_ignores_key_order() {
+        let mut x = NbtCompound::new();
+        x.put_int("a", 1);
+        x.put_int("b", 2);
+        let mut y = NbtCompound::new();
+        y.put_int("b", 2);
+        y.put_int("a", 1);
+        let mut z = NbtCompound::new();
+        z.put_int("a", 1);
+        z.put_int("b", 3);
+
+        assert_eq!(x.partial_cmp(&y), Some(Ordering::Equal));
+        assert!(x < z);
+        assert!(y < z);
+        assert!(NbtTag::Compound(y) < NbtTag::Compound(z));
     }
 }
@@ -3,7 +3,7 @@ use std::io::{Seek, SeekFrom};
 
 use crate::{
     BYTE_ARRAY_ID, BYTE_ID, COMPOUND_ID, END_ID, Error, INT_ARRAY_ID, INT_ID, LIST_ID,
-    LONG_ARRAY_ID, LONG_ID, NbtTag, get_nbt_string, io,
+    LONG_ARRAY_ID, LONG_ID, MAX_PREALLOCATED_LEN, NbtTag, get_nbt_string, io,
 };
 use io::Read;
 use serde::de::{self, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor};
@@ -25,16 +25,10 @@ pub(super) fn set_curr_visitor_seq_list_id(tag: Option<u8>) {
     });
 }
 
-pub struct NbtReadHelper<R: Read + Seek> {
+pub struct NbtReadHelper<R: Read> {
     reader: R,
 }
 
-impl<R: Read + Seek> NbtReadHelper<R> {
-    pub const fn new(r: R) -> Self {
-        Self { reader: r }
-    }
-}
-
 macro_rules! define_get_number_be {
     ($name:ident, $type:ty) => {
         pub fn $name(&mut self) -> Result<$type> {
@@ -55,6 +49,12 @@ impl<R: Read + Seek> NbtReadHelper<R> {
             .map_err(Error::Incomplete)?;
         Ok(())
     }
+}
+
+impl<R: Read> NbtReadHelper<R> {
+    pub const fn new(r: R) -> Self {
+        Self { reader: r }
+    }
 
     define_get_number_be!(get_u8_be, u8);
     define_get_number_be!(get_i8_be, i8);
@@ -68,10 +68,15 @@ impl<R: Read + Seek> NbtReadHelper<R> {
     define_get_number_be!(get_f64_be, f64);
 
     pub fn read_boxed_slice(&mut self, count: usize) -> Result<Box<[u8]>> {
-        let mut buf = vec![0u8; count];
-        self.reader
-            .read_exact(&mut buf)
+        // The buffer grows as data arrives instead of trusting `count` up front
+        let mut buf = Vec::with_capacity(count.min(MAX_PREALLOCATED_LEN));
+        (&mut self.reader)
+            .take(count as u64)
+            .read_to_end(&mut buf)
             .map_err(Error::Incomplete)?;
+        if buf.len() < count {
+            return Err(Error::Incomplete(io::ErrorKind::UnexpectedEof.into()));
+        }
 
         Ok(buf.into())
     }
@@ -14,9 +14,15 @@ use thiserror::Error;
 
 pub mod compound;
 pub mod deserializer;
+#[cfg(feature = "flate2")]
 pub mod nbt_compress;
+pub mod path;
 pub mod serializer;
+pub mod snbt;
 pub mod tag;
+#[cfg(test)]
+mod test_util;
+pub mod visitor;
 
 pub use deserializer::{from_bytes, from_bytes_unnamed};
 pub use serializer::{to_bytes, to_bytes_named, to_bytes_unnamed};
@@ -37,6 +43,13 @@ pub const COMPOUND_ID: u8 = 0x0A;
 pub const INT_ARRAY_ID: u8 = 0x0B;
 pub const LONG_ARRAY_ID: u8 = 0x0C;
 
+/// Same nesting limit as vanilla, prevents stack overflows on deeply nested input.
+pub(crate) const MAX_DEPTH: usize = 512;
+
+/// Upper bound on the capacity reserved for a length read from the input, so a bogus length
+/// can't cause a huge allocation before the data behind it has been read.
+pub(crate) const MAX_PREALLOCATED_LEN: usize = 4096;
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("The root tag of the NBT file is not a compound tag. Received tag id: {0}")]
@@ -55,6 +68,10 @@ pub enum Error {
     NegativeLength(i32),
     #[error("Length too large: {0}")]
     LargeLength(usize),
+    #[error("Tags are nested deeper than {0} levels")]
+    TooDeep(usize),
+    #[error("List of End tags with a non-zero length: {0}")]
+    NonEmptyEndList(i32),
 }
 
 impl ser::Error for Error {
@@ -69,7 +86,7 @@ impl de::Error for Error {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq, PartialOrd)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd)]
 pub struct Nbt {
     pub name: String,
     pub root_tag: NbtCompound,
@@ -116,9 +133,7 @@ impl Nbt {
         let mut bytes = Vec::new();
         let mut writer = WriteAdaptor::new(&mut bytes);
         writer.write_u8_be(COMPOUND_ID).unwrap();
-        NbtTag::String(self.name)
-            .serialize_data(&mut writer)
-            .unwrap();
+        NbtTag::write_string(&self.name, &mut writer).unwrap();
         self.root_tag.serialize_content(&mut writer).unwrap();
 
         bytes.into()
@@ -177,7 +192,7 @@ impl AsMut<NbtCompound> for Nbt {
     }
 }
 
-pub fn get_nbt_string<R: Read + Seek>(bytes: &mut NbtReadHelper<R>) -> Result<String, Error> {
+pub fn get_nbt_string<R: Read>(bytes: &mut NbtReadHelper<R>) -> Result<String, Error> {
     let len = bytes.get_u16_be()? as usize;
     let string_bytes = bytes.read_boxed_slice(len)?;
     let string = cesu8::from_java_cesu8(&string_bytes).map_err(|_| Error::Cesu8DecodingError)?;
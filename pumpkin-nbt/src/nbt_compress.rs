@@ -1,8 +1,15 @@
 use crate::deserializer::NbtReadHelper;
+use crate::tag::NbtTag;
 use crate::{Error, Nbt, NbtCompound, deserializer, serializer};
-use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use flate2::{
+    Compression,
+    read::{GzDecoder, ZlibDecoder},
+    write::GzEncoder,
+};
 use std::io::{Cursor, Read, Seek, Write};
 
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
 /// Reads a `GZipped` NBT compound tag from any reader.
 ///
 /// # Arguments
@@ -100,6 +107,38 @@ pub fn to_gzip_bytes_vec<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, Erro
     Ok(buffer)
 }
 
+impl NbtTag {
+    /// Reads a named tag that may be `GZip` or `Zlib` compressed, or not compressed at all.
+    ///
+    /// The compression is detected from the first bytes of the input,
+    /// so `.dat` files and plain NBT can be read with the same function.
+    pub fn read_compressed<R: Read>(reader: &mut R) -> Result<(String, Self), Error> {
+        let mut header = Vec::with_capacity(2);
+        reader
+            .take(2)
+            .read_to_end(&mut header)
+            .map_err(Error::Incomplete)?;
+        let mut input = Cursor::new(header.as_slice()).chain(reader);
+
+        match header.as_slice() {
+            [a, b] if [*a, *b] == GZIP_MAGIC => Self::read_from(&mut GzDecoder::new(input)),
+            // Zlib streams start with 0x78 and a checksum that makes the first two bytes divisible by 31
+            [0x78, b] if (0x7800 | u16::from(*b)) % 31 == 0 => {
+                Self::read_from(&mut ZlibDecoder::new(input))
+            }
+            _ => Self::read_from(&mut input),
+        }
+    }
+
+    /// Writes this tag as a named tag with `GZip` compression, the format used by `.dat` files.
+    pub fn write_compressed<W: Write>(&self, writer: &mut W, root_name: &str) -> Result<(), Error> {
+        let mut encoder = GzEncoder::new(writer, Compression::default());
+        self.write_to(&mut encoder, root_name)?;
+        encoder.finish().map_err(Error::Incomplete)?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -109,11 +148,16 @@ mod tests {
             write_gzip_compound_tag, write_gzip_compound_tag_to_bytes,
         },
         tag::NbtTag,
+        test_util::sample_compound,
+    };
+    use flate2::{
+        Compression,
+        write::{GzEncoder, ZlibEncoder},
     };
     use serde::{Deserialize, Serialize};
     use std::collections::HashMap;
     use std::fs::File;
-    use std::io::Cursor;
+    use std::io::{Cursor, Write};
 
     #[test]
     fn gzip_read_write_compound() {
@@ -366,4 +410,48 @@ mod tests {
 
         assert_eq!(read_compound.get_int("test_value"), Some(42));
     }
+
+    #[test]
+    fn tag_compressed_round_trip() {
+        let tag = NbtTag::Compound(sample_compound());
+        let mut buffer = Vec::new();
+        tag.write_compressed(&mut buffer, "").unwrap();
+        assert_eq!(buffer[..2], [0x1f, 0x8b]);
+
+        let (name, read) = NbtTag::read_compressed(&mut buffer.as_slice()).unwrap();
+        assert_eq!(name, "");
+        assert_eq!(read, tag);
+    }
+
+    #[test]
+    fn tag_compressed_detects_zlib_and_plain() {
+        let tag = NbtTag::Compound(sample_compound());
+        let mut plain = Vec::new();
+        tag.write_to(&mut plain, "plain").unwrap();
+        let (name, read) = NbtTag::read_compressed(&mut plain.as_slice()).unwrap();
+        assert_eq!((name.as_str(), &read), ("plain", &tag));
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&plain).unwrap();
+        let zlib = encoder.finish().unwrap();
+        let (name, read) = NbtTag::read_compressed(&mut zlib.as_slice()).unwrap();
+        assert_eq!((name.as_str(), &read), ("plain", &tag));
+
+        let (_, read) = NbtTag::read_compressed(&mut [0u8].as_slice()).unwrap();
+        assert_eq!(read, NbtTag::End);
+    }
+
+    #[test]
+    fn tag_compressed_missing_end_tag() {
+        let mut plain = Vec::new();
+        NbtTag::Compound(sample_compound())
+            .write_to(&mut plain, "")
+            .unwrap();
+        plain.pop();
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&plain).unwrap();
+        let gzip = encoder.finish().unwrap();
+        assert!(NbtTag::read_compressed(&mut gzip.as_slice()).is_err());
+    }
 }
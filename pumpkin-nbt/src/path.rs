@@ -0,0 +1,204 @@
+//! Paths like `a.b[2].c` for looking up deeply nested tags.
+
+use std::borrow::Cow;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::tag::NbtTag;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NbtPathSegment {
+    /// A key into a compound.
+    Key(String),
+    /// An index into a list or array.
+    Index(usize),
+}
+
+/// A path through nested compounds, lists and arrays, parsed from strings like `a.b[2].c`.
+///
+/// Keys are separated by `.` and may not contain `.`, `[` or `]`.
+/// Indices are written in brackets after a key, or at the start of the path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NbtPath {
+    pub segments: Vec<NbtPathSegment>,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum NbtPathParseError {
+    #[error("Expected a key at position {0}")]
+    EmptyKey(usize),
+    #[error("Invalid index at position {0}")]
+    InvalidIndex(usize),
+    #[error("Unclosed '[' at position {0}")]
+    UnclosedBracket(usize),
+    #[error("Unexpected character '{1}' at position {0}")]
+    UnexpectedChar(usize, char),
+}
+
+impl FromStr for NbtPath {
+    type Err = NbtPathParseError;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        let mut segments = Vec::new();
+        let mut pos = 0;
+        // A key is expected at the start of the path and after every '.'
+        let mut expect_key = !path.starts_with('[');
+
+        while pos < path.len() || expect_key {
+            if expect_key {
+                let len = path[pos..]
+                    .find(['.', '[', ']'])
+                    .unwrap_or(path.len() - pos);
+                if len == 0 {
+                    return Err(NbtPathParseError::EmptyKey(pos));
+                }
+                segments.push(NbtPathSegment::Key(path[pos..pos + len].to_string()));
+                pos += len;
+                expect_key = false;
+                continue;
+            }
+
+            match path[pos..].chars().next() {
+                Some('.') => {
+                    pos += 1;
+                    expect_key = true;
+                }
+                Some('[') => {
+                    let Some(len) = path[pos + 1..].find(']') else {
+                        return Err(NbtPathParseError::UnclosedBracket(pos));
+                    };
+                    let index = &path[pos + 1..pos + 1 + len];
+                    if !index.bytes().all(|b| b.is_ascii_digit()) {
+                        return Err(NbtPathParseError::InvalidIndex(pos + 1));
+                    }
+                    let index = index
+                        .parse()
+                        .map_err(|_| NbtPathParseError::InvalidIndex(pos + 1))?;
+                    segments.push(NbtPathSegment::Index(index));
+                    pos += len + 2;
+                }
+                Some(c) => return Err(NbtPathParseError::UnexpectedChar(pos, c)),
+                None => break,
+            }
+        }
+
+        Ok(Self { segments })
+    }
+}
+
+impl NbtTag {
+    /// Follows `path` through compounds by key and through lists and arrays by index.
+    ///
+    /// Indexing into a `ByteArray`, `IntArray` or `LongArray` as the last segment of the path
+    /// returns a new `Byte`, `Int` or `Long` tag holding the element, hence the `Cow`.
+    #[must_use]
+    pub fn get_path(&self, path: &NbtPath) -> Option<Cow<'_, Self>> {
+        let mut current = self;
+        let mut segments = path.segments.iter().peekable();
+
+        while let Some(segment) = segments.next() {
+            current = match (current, segment) {
+                (Self::Compound(compound), NbtPathSegment::Key(key)) => compound.get(key)?,
+                (Self::List(list), NbtPathSegment::Index(index)) => list.get(*index)?,
+                (Self::ByteArray(array), NbtPathSegment::Index(index))
+                    if segments.peek().is_none() =>
+                {
+                    return array
+                        .get(*index)
+                        .map(|byte| Cow::Owned(Self::Byte(*byte as i8)));
+                }
+                (Self::IntArray(array), NbtPathSegment::Index(index))
+                    if segments.peek().is_none() =>
+                {
+                    return array.get(*index).map(|int| Cow::Owned(Self::Int(*int)));
+                }
+                (Self::LongArray(array), NbtPathSegment::Index(index))
+                    if segments.peek().is_none() =>
+                {
+                    return array.get(*index).map(|long| Cow::Owned(Self::Long(*long)));
+                }
+                _ => return None,
+            };
+        }
+
+        Some(Cow::Borrowed(current))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+
+    use crate::path::{NbtPath, NbtPathParseError, NbtPathSegment};
+    use crate::tag::NbtTag;
+    use crate::test_util::sample_compound;
+
+    fn path(path: &str) -> NbtPath {
+        path.parse().unwrap()
+    }
+
+    fn sample() -> NbtTag {
+        NbtTag::Compound(sample_compound())
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            path("a.b[2].c").segments,
+            [
+                NbtPathSegment::Key("a".to_string()),
+                NbtPathSegment::Key("b".to_string()),
+                NbtPathSegment::Index(2),
+                NbtPathSegment::Key("c".to_string()),
+            ]
+        );
+        assert_eq!(
+            path("[0][1]").segments,
+            [NbtPathSegment::Index(0), NbtPathSegment::Index(1)]
+        );
+        assert_eq!(
+            "a..b".parse::<NbtPath>(),
+            Err(NbtPathParseError::EmptyKey(2))
+        );
+        assert_eq!("".parse::<NbtPath>(), Err(NbtPathParseError::EmptyKey(0)));
+        assert_eq!("a.".parse::<NbtPath>(), Err(NbtPathParseError::EmptyKey(2)));
+        assert_eq!(
+            "a[1".parse::<NbtPath>(),
+            Err(NbtPathParseError::UnclosedBracket(1))
+        );
+        assert_eq!(
+            "a[-1]".parse::<NbtPath>(),
+            Err(NbtPathParseError::InvalidIndex(2))
+        );
+        assert_eq!(
+            "a[1]b".parse::<NbtPath>(),
+            Err(NbtPathParseError::UnexpectedChar(4, 'b'))
+        );
+    }
+
+    #[test]
+    fn get_valid_path() {
+        let tag = sample();
+        assert_eq!(
+            tag.get_path(&path("items[1].id")),
+            Some(Cow::Borrowed(&NbtTag::String(
+                "minecraft:stone".to_string()
+            )))
+        );
+        assert_eq!(
+            tag.get_path(&path("items[1].ints[2]")),
+            Some(Cow::Owned(NbtTag::Int(i32::MAX)))
+        );
+    }
+
+    #[test]
+    fn get_missing_path() {
+        let tag = sample();
+        assert_eq!(tag.get_path(&path("inner key.armor")), None);
+        assert_eq!(tag.get_path(&path("items[2].id")), None);
+        assert_eq!(tag.get_path(&path("items[1].ints[3]")), None);
+        assert_eq!(tag.get_path(&path("items[1].ints[0].x")), None);
+        assert_eq!(tag.get_path(&path("inner key[0]")), None);
+    }
+}
@@ -0,0 +1,561 @@
+//! Stringified NBT (SNBT), the textual form of NBT used by Minecraft commands.
+
+use std::fmt::{Debug, Display, Formatter, Write};
+
+use thiserror::Error;
+
+use crate::compound::NbtCompound;
+use crate::tag::NbtTag;
+use crate::{BYTE_ID, INT_ID, LONG_ID, MAX_DEPTH};
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{reason} at position {offset}")]
+pub struct SnbtParseError {
+    /// Byte offset into the input where the error was detected.
+    pub offset: usize,
+    pub reason: String,
+}
+
+impl NbtTag {
+    /// Returns the canonical SNBT representation of this tag, e.g. `{foo:1b,bar:"baz"}`.
+    ///
+    /// This is the same text produced by the `Display` implementation.
+    #[must_use]
+    pub fn to_snbt(&self) -> String {
+        self.to_string()
+    }
+
+    /// Parses a tag from its SNBT representation.
+    ///
+    /// Numbers without a suffix are read as `Int`, or `Double` if they contain a `.`,
+    /// and `true`/`false` are read as bytes. Unquoted words that aren't numbers are strings.
+    pub fn from_snbt(input: &str) -> Result<Self, SnbtParseError> {
+        let mut parser = SnbtParser { input, pos: 0 };
+        parser.skip_whitespace();
+        let tag = parser.parse_value(0)?;
+        parser.skip_whitespace();
+        if parser.pos < input.len() {
+            return Err(parser.error("Trailing data after value"));
+        }
+        Ok(tag)
+    }
+}
+
+impl NbtCompound {
+    /// Returns the canonical SNBT representation of this compound.
+    #[must_use]
+    pub fn to_snbt(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Returns whether `key` can be written without quotes.
+fn is_simple_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+'))
+}
+
+/// Writes `string` surrounded by quotes, escaping backslashes and the quote character.
+///
+/// Like vanilla, double quotes are used unless the string contains a double quote
+/// before any single quote, in which case single quotes are used instead.
+fn write_quoted(f: &mut Formatter<'_>, string: &str) -> std::fmt::Result {
+    let quote = match string.chars().find(|c| matches!(c, '"' | '\'')) {
+        Some('"') => '\'',
+        _ => '"',
+    };
+
+    f.write_char(quote)?;
+    for c in string.chars() {
+        if c == '\\' || c == quote {
+            f.write_char('\\')?;
+        }
+        f.write_char(c)?;
+    }
+    f.write_char(quote)
+}
+
+/// Writes a float with its type suffix, spelling non-finite values like Java does
+/// (`NaN`, `Infinity`, `-Infinity`) so the parser can read them back.
+fn write_decimal<T: Debug + Into<f64> + Copy>(
+    f: &mut Formatter<'_>,
+    value: T,
+    suffix: char,
+) -> std::fmt::Result {
+    let wide: f64 = value.into();
+    if wide.is_nan() {
+        f.write_str("NaN")?;
+    } else if wide.is_infinite() {
+        f.write_str(if wide > 0.0 { "Infinity" } else { "-Infinity" })?;
+    } else {
+        write!(f, "{value:?}")?;
+    }
+    f.write_char(suffix)
+}
+
+fn write_joined<T>(
+    f: &mut Formatter<'_>,
+    values: impl IntoIterator<Item = T>,
+    mut write: impl FnMut(&mut Formatter<'_>, T) -> std::fmt::Result,
+) -> std::fmt::Result {
+    for (i, value) in values.into_iter().enumerate() {
+        if i > 0 {
+            f.write_char(',')?;
+        }
+        write(f, value)?;
+    }
+    Ok(())
+}
+
+/// SNBT display implementation for `NbtCompound`
+impl Display for NbtCompound {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_char('{')?;
+        write_joined(f, &self.child_tags, |f, (key, value)| {
+            if is_simple_key(key) {
+                f.write_str(key)?;
+            } else {
+                write_quoted(f, key)?;
+            }
+            write!(f, ":{value}")
+        })?;
+        f.write_char('}')
+    }
+}
+
+impl Display for NbtTag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::End => Ok(()),
+            Self::Byte(v) => write!(f, "{v}b"),
+            Self::Short(v) => write!(f, "{v}s"),
+            Self::Int(v) => write!(f, "{v}"),
+            Self::Long(v) => write!(f, "{v}L"),
+            Self::Float(v) => write_decimal(f, *v, 'f'),
+            Self::Double(v) => write_decimal(f, *v, 'd'),
+            Self::String(v) => write_quoted(f, v),
+            Self::Compound(v) => write!(f, "{v}"),
+            Self::ByteArray(v) => {
+                f.write_str("[B;")?;
+                write_joined(f, v.iter(), |f, byte| write!(f, "{}B", *byte as i8))?;
+                f.write_char(']')
+            }
+            Self::List(v) => {
+                f.write_char('[')?;
+                write_joined(f, v, |f, tag| write!(f, "{tag}"))?;
+                f.write_char(']')
+            }
+            Self::IntArray(v) => {
+                f.write_str("[I;")?;
+                write_joined(f, v, |f, int| write!(f, "{int}"))?;
+                f.write_char(']')
+            }
+            Self::LongArray(v) => {
+                f.write_str("[L;")?;
+                write_joined(f, v, |f, long| write!(f, "{long}L"))?;
+                f.write_char(']')
+            }
+        }
+    }
+}
+
+struct SnbtParser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl SnbtParser<'_> {
+    fn error(&self, reason: &str) -> SnbtParseError {
+        SnbtParseError {
+            offset: self.pos,
+            reason: reason.to_string(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.pos += c.len_utf8();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), SnbtParseError> {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected '{expected}'")))
+        }
+    }
+
+    fn parse_value(&mut self, depth: usize) -> Result<NbtTag, SnbtParseError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("Tag is nested too deeply"));
+        }
+        match self.peek() {
+            Some('{') => self.parse_compound(depth).map(NbtTag::Compound),
+            Some('[') => self.parse_list_or_array(depth),
+            Some('"' | '\'') => self.parse_quoted().map(NbtTag::String),
+            _ => self.parse_unquoted(),
+        }
+    }
+
+    fn parse_compound(&mut self, depth: usize) -> Result<NbtCompound, SnbtParseError> {
+        self.expect('{')?;
+        let mut compound = NbtCompound::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(compound);
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = match self.peek() {
+                Some('"' | '\'') => self.parse_quoted()?,
+                Some('}') => return Err(self.error("Trailing comma in compound")),
+                _ => {
+                    let key = self.read_unquoted();
+                    if key.is_empty() {
+                        return Err(self.error("Expected key"));
+                    }
+                    key.to_string()
+                }
+            };
+            self.skip_whitespace();
+            self.expect(':')?;
+            self.skip_whitespace();
+            let value = self.parse_value(depth + 1)?;
+            // Like vanilla, the last value of a repeated key wins
+            match compound
+                .child_tags
+                .iter_mut()
+                .find(|(name, _)| *name == key)
+            {
+                Some((_, existing)) => *existing = value,
+                None => compound.child_tags.push((key, value)),
+            }
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {
+                    self.pos += 1;
+                    return Ok(compound);
+                }
+                _ => return Err(self.error("Expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn parse_list_or_array(&mut self, depth: usize) -> Result<NbtTag, SnbtParseError> {
+        self.expect('[')?;
+        let rest = &self.input[self.pos..];
+        let array_type = match rest.as_bytes() {
+            [b'B', b';', ..] => Some(BYTE_ID),
+            [b'I', b';', ..] => Some(INT_ID),
+            [b'L', b';', ..] => Some(LONG_ID),
+            _ => None,
+        };
+        if array_type.is_some() {
+            self.pos += 2;
+        }
+
+        let mut elements = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.pos += 1;
+        } else {
+            loop {
+                self.skip_whitespace();
+                if self.peek() == Some(']') {
+                    return Err(self.error("Trailing comma in list"));
+                }
+                let start = self.pos;
+                let element = self.parse_value(depth + 1)?;
+                let expected = array_type.or_else(|| elements.first().map(NbtTag::get_type_id));
+                if expected.is_some_and(|id| id != element.get_type_id()) {
+                    return Err(SnbtParseError {
+                        offset: start,
+                        reason: "Elements must all be of the same type".to_string(),
+                    });
+                }
+                elements.push(element);
+
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(',') => self.pos += 1,
+                    Some(']') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    _ => return Err(self.error("Expected ',' or ']'")),
+                }
+            }
+        }
+
+        Ok(match array_type {
+            Some(BYTE_ID) => NbtTag::ByteArray(
+                elements
+                    .iter()
+                    .filter_map(|tag| tag.extract_byte().map(|byte| byte as u8))
+                    .collect(),
+            ),
+            Some(INT_ID) => {
+                NbtTag::IntArray(elements.iter().filter_map(NbtTag::extract_int).collect())
+            }
+            Some(_) => {
+                NbtTag::LongArray(elements.iter().filter_map(NbtTag::extract_long).collect())
+            }
+            None => NbtTag::List(elements),
+        })
+    }
+
+    fn parse_quoted(&mut self) -> Result<String, SnbtParseError> {
+        let start = self.pos;
+        let Some(quote) = self.peek() else {
+            return Err(self.error("Expected string"));
+        };
+        self.pos += 1;
+
+        let mut string = String::new();
+        let mut chars = self.input[self.pos..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            if c == quote {
+                self.pos += i + 1;
+                return Ok(string);
+            }
+            if c == '\\' {
+                match chars.next() {
+                    Some((_, escaped @ ('\\' | '"' | '\''))) => string.push(escaped),
+                    Some((j, _)) => {
+                        self.pos += j;
+                        return Err(self.error("Invalid escape sequence"));
+                    }
+                    None => break,
+                }
+            } else {
+                string.push(c);
+            }
+        }
+
+        Err(SnbtParseError {
+            offset: start,
+            reason: "Unterminated string".to_string(),
+        })
+    }
+
+    fn read_unquoted(&mut self) -> &str {
+        let start = self.pos;
+        while let Some(c) = self
+            .peek()
+            .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+'))
+        {
+            self.pos += c.len_utf8();
+        }
+        &self.input[start..self.pos]
+    }
+
+    fn parse_unquoted(&mut self) -> Result<NbtTag, SnbtParseError> {
+        let token = self.read_unquoted();
+        if token.is_empty() {
+            return Err(self.error("Expected value"));
+        }
+        Ok(parse_unquoted_token(token))
+    }
+}
+
+/// Infers the type of an unquoted token the same way vanilla does,
+/// falling back to a string if it isn't a valid number.
+fn parse_unquoted_token(token: &str) -> NbtTag {
+    match token {
+        "true" => return NbtTag::Byte(1),
+        "false" => return NbtTag::Byte(0),
+        _ => {}
+    }
+
+    let is_integer = |body: &str| {
+        let digits = body.strip_prefix(['-', '+']).unwrap_or(body);
+        !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+    };
+    let is_decimal = |body: &str| {
+        body.bytes().any(|b| b.is_ascii_digit())
+            && body
+                .bytes()
+                .all(|b| b.is_ascii_digit() || matches!(b, b'.' | b'e' | b'E' | b'-' | b'+'))
+    };
+    // Only accepted with a type suffix, a bare `NaN` stays a string
+    let is_suffixed_decimal =
+        |body: &str| is_decimal(body) || matches!(body, "NaN" | "Infinity" | "-Infinity");
+
+    let (body, suffix) = token.split_at(token.len() - 1);
+    let tag = match suffix {
+        "b" | "B" if is_integer(body) => body.parse().ok().map(NbtTag::Byte),
+        "s" | "S" if is_integer(body) => body.parse().ok().map(NbtTag::Short),
+        "l" | "L" if is_integer(body) => body.parse().ok().map(NbtTag::Long),
+        "f" | "F" if is_suffixed_decimal(body) => body.parse().ok().map(NbtTag::Float),
+        "d" | "D" if is_suffixed_decimal(body) => body.parse().ok().map(NbtTag::Double),
+        _ if is_integer(token) => token.parse().ok().map(NbtTag::Int),
+        _ if token.contains('.') && is_decimal(token) => token.parse().ok().map(NbtTag::Double),
+        _ => None,
+    };
+    tag.unwrap_or_else(|| NbtTag::String(token.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::compound::NbtCompound;
+    use crate::tag::NbtTag;
+    use crate::test_util::sample_compound;
+
+    #[test]
+    fn primitives() {
+        assert_eq!(NbtTag::Byte(-3).to_snbt(), "-3b");
+        assert_eq!(NbtTag::Short(300).to_snbt(), "300s");
+        assert_eq!(NbtTag::Int(7).to_snbt(), "7");
+        assert_eq!(NbtTag::Long(-9).to_snbt(), "-9L");
+        assert_eq!(NbtTag::Float(1.5).to_snbt(), "1.5f");
+        assert_eq!(NbtTag::Float(0.1).to_snbt(), "0.1f");
+        assert_eq!(NbtTag::Double(2.0).to_snbt(), "2.0d");
+        assert_eq!(NbtTag::String("baz".to_string()).to_snbt(), "\"baz\"");
+    }
+
+    #[test]
+    fn non_finite_floats() {
+        let tags = [
+            NbtTag::Float(f32::NAN),
+            NbtTag::Float(f32::NEG_INFINITY),
+            NbtTag::Double(f64::INFINITY),
+            NbtTag::Double(-f64::NAN),
+        ];
+        let snbt = tags.each_ref().map(NbtTag::to_snbt);
+        assert_eq!(snbt, ["NaNf", "-Infinityf", "Infinityd", "NaNd"]);
+        for (tag, snbt) in tags.iter().zip(&snbt) {
+            assert_eq!(&NbtTag::from_snbt(snbt).unwrap(), tag);
+        }
+        assert_eq!(
+            NbtTag::from_snbt("NaN").unwrap(),
+            NbtTag::String("NaN".to_string())
+        );
+    }
+
+    #[test]
+    fn arrays_and_lists() {
+        assert_eq!(
+            NbtTag::ByteArray(vec![1, 255].into_boxed_slice()).to_snbt(),
+            "[B;1B,-1B]"
+        );
+        assert_eq!(NbtTag::IntArray(vec![1, -2]).to_snbt(), "[I;1,-2]");
+        assert_eq!(NbtTag::LongArray(vec![3]).to_snbt(), "[L;3L]");
+        assert_eq!(NbtTag::IntArray(vec![]).to_snbt(), "[I;]");
+        assert_eq!(
+            NbtTag::List(vec![NbtTag::Short(1), NbtTag::Short(2)]).to_snbt(),
+            "[1s,2s]"
+        );
+    }
+
+    #[test]
+    fn nested_compound() {
+        let mut inner = NbtCompound::new();
+        inner.put_string("id", "minecraft:stone".to_string());
+        let mut compound = NbtCompound::new();
+        compound.put_byte("foo", 1);
+        compound.put_string("bar", "baz".to_string());
+        compound.put_component("inner", inner);
+        compound.put_int("needs quotes", 2);
+        compound.put_int("", 3);
+
+        assert_eq!(
+            compound.to_snbt(),
+            r#"{foo:1b,bar:"baz",inner:{id:"minecraft:stone"},"needs quotes":2,"":3}"#
+        );
+        assert_eq!(
+            NbtTag::Compound(compound.clone()).to_snbt(),
+            compound.to_string()
+        );
+    }
+
+    #[test]
+    fn string_escaping() {
+        assert_eq!(
+            NbtTag::String(r"back\slash".to_string()).to_snbt(),
+            r#""back\\slash""#
+        );
+        assert_eq!(
+            NbtTag::String(r#"say "hi""#.to_string()).to_snbt(),
+            r#"'say "hi"'"#
+        );
+        assert_eq!(
+            NbtTag::String(r#"it's "odd""#.to_string()).to_snbt(),
+            r#""it's \"odd\"""#
+        );
+        assert_eq!(
+            NbtTag::String(r#"say "it's""#.to_string()).to_snbt(),
+            r#"'say "it\'s"'"#
+        );
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        let tag = NbtTag::Compound(sample_compound());
+        assert_eq!(NbtTag::from_snbt(&tag.to_snbt()).unwrap(), tag);
+    }
+
+    #[test]
+    fn parse_loose_syntax() {
+        let tag = NbtTag::from_snbt(
+            " { a : 1 , 'b': \"x\\\\y\" , c:[ 1.5 , 2. ], d:true, e:stone, f:3.0e2f } ",
+        )
+        .unwrap();
+        let NbtTag::Compound(compound) = tag else {
+            panic!("Expected a compound");
+        };
+        assert_eq!(compound.get_int("a"), Some(1));
+        assert_eq!(compound.get_string("b"), Some("x\\y"));
+        assert_eq!(
+            compound.get_list("c"),
+            Some([NbtTag::Double(1.5), NbtTag::Double(2.0)].as_slice())
+        );
+        assert_eq!(compound.get_bool("d"), Some(true));
+        assert_eq!(compound.get_string("e"), Some("stone"));
+        assert_eq!(compound.get_float("f"), Some(300.0));
+    }
+
+    #[test]
+    fn parse_duplicate_keys() {
+        let tag = NbtTag::from_snbt("{a:1,b:2,a:3}").unwrap();
+        let NbtTag::Compound(compound) = tag else {
+            panic!("Expected a compound");
+        };
+        assert_eq!(compound.child_tags.len(), 2);
+        assert_eq!(compound.get_int("a"), Some(3));
+        assert_eq!(compound.to_snbt(), "{a:3,b:2}");
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = NbtTag::from_snbt("{a:\"abc}").unwrap_err();
+        assert_eq!(error.offset, 3);
+        assert_eq!(error.reason, "Unterminated string");
+
+        let error = NbtTag::from_snbt("{a:1,}").unwrap_err();
+        assert_eq!(error.offset, 5);
+
+        let error = NbtTag::from_snbt("[1,2,]").unwrap_err();
+        assert_eq!(error.offset, 5);
+
+        let error = NbtTag::from_snbt("[I;1,2b]").unwrap_err();
+        assert_eq!(error.offset, 5);
+        assert_eq!(error.reason, "Elements must all be of the same type");
+
+        assert!(NbtTag::from_snbt("[1,\"two\"]").is_err());
+        assert!(NbtTag::from_snbt("{a:1} b").is_err());
+        assert!(NbtTag::from_snbt("{a 1}").is_err());
+        assert!(NbtTag::from_snbt("").is_err());
+    }
+}
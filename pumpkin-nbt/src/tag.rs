@@ -3,14 +3,17 @@ use deserializer::NbtReadHelper;
 use io::Read;
 use serde::{Deserialize, Serialize};
 use serializer::WriteAdaptor;
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
 
 use crate::{
     BYTE_ARRAY_ID, BYTE_ID, COMPOUND_ID, DOUBLE_ID, END_ID, Error, FLOAT_ID, INT_ARRAY_ID, INT_ID,
-    LIST_ID, LONG_ARRAY_ID, LONG_ID, SHORT_ID, STRING_ID, Seek, Write, compound, deserializer,
-    get_nbt_string, io, nbt_byte_array, nbt_int_array, nbt_long_array, serializer,
+    LIST_ID, LONG_ARRAY_ID, LONG_ID, MAX_DEPTH, MAX_PREALLOCATED_LEN, SHORT_ID, STRING_ID, Seek,
+    Write, compound, deserializer, get_nbt_string, io, nbt_byte_array, nbt_int_array,
+    nbt_long_array, serializer,
 };
 
-#[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[derive(Clone, Debug)]
 #[repr(u8)]
 pub enum NbtTag {
     End = END_ID,
@@ -37,7 +40,7 @@ impl NbtTag {
         unsafe { *std::ptr::from_ref::<Self>(self).cast::<u8>() }
     }
 
-    pub fn serialize<W: Write>(self, w: &mut WriteAdaptor<W>) -> serializer::Result<()> {
+    pub fn serialize<W: Write>(&self, w: &mut WriteAdaptor<W>) -> serializer::Result<()> {
         w.write_u8_be(self.get_type_id())?;
         self.serialize_data(w)?;
         Ok(())
@@ -55,15 +58,15 @@ impl NbtTag {
         Ok(())
     }
 
-    pub fn serialize_data<W: Write>(self, w: &mut WriteAdaptor<W>) -> serializer::Result<()> {
+    pub fn serialize_data<W: Write>(&self, w: &mut WriteAdaptor<W>) -> serializer::Result<()> {
         match self {
             Self::End => {}
-            Self::Byte(byte) => w.write_i8_be(byte)?,
-            Self::Short(short) => w.write_i16_be(short)?,
-            Self::Int(int) => w.write_i32_be(int)?,
-            Self::Long(long) => w.write_i64_be(long)?,
-            Self::Float(float) => w.write_f32_be(float)?,
-            Self::Double(double) => w.write_f64_be(double)?,
+            Self::Byte(byte) => w.write_i8_be(*byte)?,
+            Self::Short(short) => w.write_i16_be(*short)?,
+            Self::Int(int) => w.write_i32_be(*int)?,
+            Self::Long(long) => w.write_i64_be(*long)?,
+            Self::Float(float) => w.write_f32_be(*float)?,
+            Self::Double(double) => w.write_f64_be(*double)?,
             Self::ByteArray(byte_array) => {
                 let len = byte_array.len();
                 if len > i32::MAX as usize {
@@ -71,10 +74,10 @@ impl NbtTag {
                 }
 
                 w.write_i32_be(len as i32)?;
-                w.write_slice(&byte_array)?;
+                w.write_slice(byte_array)?;
             }
             Self::String(string) => {
-                Self::write_string(&string, w)?;
+                Self::write_string(string, w)?;
             }
             Self::List(list) => {
                 let len = list.len();
@@ -99,7 +102,7 @@ impl NbtTag {
 
                 w.write_i32_be(len as i32)?;
                 for int in int_array {
-                    w.write_i32_be(int)?;
+                    w.write_i32_be(*int)?;
                 }
             }
             Self::LongArray(long_array) => {
@@ -111,18 +114,95 @@ impl NbtTag {
                 w.write_i32_be(len as i32)?;
 
                 for long in long_array {
-                    w.write_i64_be(long)?;
+                    w.write_i64_be(*long)?;
                 }
             }
         }
         Ok(())
     }
 
-    pub fn deserialize<R: Read + Seek>(reader: &mut NbtReadHelper<R>) -> Result<Self, Error> {
+    pub fn deserialize<R: Read>(reader: &mut NbtReadHelper<R>) -> Result<Self, Error> {
         let tag_id = reader.get_u8_be()?;
         Self::deserialize_data(reader, tag_id)
     }
 
+    /// Writes this tag in the binary NBT format: its type id, `root_name` and its payload.
+    ///
+    /// Like vanilla, an `End` tag is written as its type id alone.
+    pub fn write_to<W: Write>(&self, writer: &mut W, root_name: &str) -> Result<(), Error> {
+        let mut w = WriteAdaptor::new(writer);
+        w.write_u8_be(self.get_type_id())?;
+        if matches!(self, Self::End) {
+            return Ok(());
+        }
+        Self::write_string(root_name, &mut w)?;
+        self.serialize_data(&mut w)
+    }
+
+    /// Reads a named tag written by [`NbtTag::write_to`], returning its name and the tag.
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<(String, Self), Error> {
+        let mut reader = NbtReadHelper::new(reader);
+        let tag_id = reader.get_u8_be()?;
+        if tag_id == END_ID {
+            return Ok((String::new(), Self::End));
+        }
+        let name = get_nbt_string(&mut reader)?;
+        let tag = Self::deserialize_data(&mut reader, tag_id)?;
+        Ok((name, tag))
+    }
+
+    /// Writes this tag the way the network protocol expects it: its type id followed by
+    /// its payload, without a root name. Only compound and `End` tags are accepted as roots.
+    pub fn write_network<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        if !matches!(self, Self::Compound(_) | Self::End) {
+            return Err(Error::NoRootCompound(self.get_type_id()));
+        }
+        let mut w = WriteAdaptor::new(writer);
+        w.write_u8_be(self.get_type_id())?;
+        self.serialize_data(&mut w)
+    }
+
+    /// Returns the number of bytes [`NbtTag::write_network`] would write for this tag,
+    /// without serializing it, or `None` for the roots it rejects.
+    #[must_use]
+    pub fn network_len(&self) -> Option<usize> {
+        matches!(self, Self::Compound(_) | Self::End).then(|| 1 + self.data_len())
+    }
+
+    /// Returns the length of the payload written by [`NbtTag::serialize_data`].
+    fn data_len(&self) -> usize {
+        match self {
+            Self::End => 0,
+            Self::Byte(_) => 1,
+            Self::Short(_) => 2,
+            Self::Int(_) | Self::Float(_) => 4,
+            Self::Long(_) | Self::Double(_) => 8,
+            Self::ByteArray(byte_array) => 4 + byte_array.len(),
+            Self::String(string) => string_len(string),
+            Self::List(list) => 1 + 4 + list.iter().map(Self::data_len).sum::<usize>(),
+            Self::Compound(compound) => {
+                compound
+                    .child_tags
+                    .iter()
+                    .map(|(name, tag)| 1 + string_len(name) + tag.data_len())
+                    .sum::<usize>()
+                    + 1
+            }
+            Self::IntArray(int_array) => 4 + int_array.len() * 4,
+            Self::LongArray(long_array) => 4 + long_array.len() * 8,
+        }
+    }
+
+    /// Reads a tag written by [`NbtTag::write_network`].
+    pub fn read_network<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        let mut reader = NbtReadHelper::new(reader);
+        let tag_id = reader.get_u8_be()?;
+        if tag_id != COMPOUND_ID && tag_id != END_ID {
+            return Err(Error::NoRootCompound(tag_id));
+        }
+        Self::deserialize_data(&mut reader, tag_id)
+    }
+
     pub fn skip_data<R: Read + Seek>(
         reader: &mut NbtReadHelper<R>,
         tag_id: u8,
@@ -178,10 +258,61 @@ impl NbtTag {
         }
     }
 
-    pub fn deserialize_data<R: Read + Seek>(
+    pub fn deserialize_data<R: Read>(
         reader: &mut NbtReadHelper<R>,
         tag_id: u8,
     ) -> Result<Self, Error> {
+        Self::deserialize_nested(reader, tag_id, 0)
+    }
+
+    /// Reads the payload of a tag nested `depth` levels deep.
+    ///
+    /// Like vanilla, compounds and lists may be nested at most [`MAX_DEPTH`] levels deep.
+    /// Lengths read from the input only reserve a bounded capacity up front.
+    pub(crate) fn deserialize_nested<R: Read>(
+        reader: &mut NbtReadHelper<R>,
+        tag_id: u8,
+        depth: usize,
+    ) -> Result<Self, Error> {
+        // Everything but lists and compounds is read in a separate function
+        // to keep the stack frames of this recursion small
+        match tag_id {
+            LIST_ID => Self::deserialize_list(reader, depth).map(Self::List),
+            COMPOUND_ID => {
+                NbtCompound::deserialize_entries(reader, true, depth).map(Self::Compound)
+            }
+            _ => Self::deserialize_flat(reader, tag_id),
+        }
+    }
+
+    fn deserialize_list<R: Read>(
+        reader: &mut NbtReadHelper<R>,
+        depth: usize,
+    ) -> Result<Vec<Self>, Error> {
+        let tag_type_id = reader.get_u8_be()?;
+        let len = reader.get_i32_be()?;
+        if len < 0 {
+            return Err(Error::NegativeLength(len));
+        }
+
+        if depth >= MAX_DEPTH {
+            return Err(Error::TooDeep(MAX_DEPTH));
+        }
+        // End tags take no bytes, so a long list of them could be "read" without any input
+        if tag_type_id == END_ID && len > 0 {
+            return Err(Error::NonEmptyEndList(len));
+        }
+
+        let mut list = Vec::with_capacity((len as usize).min(MAX_PREALLOCATED_LEN));
+        for _ in 0..len {
+            let tag = Self::deserialize_nested(reader, tag_type_id, depth + 1)?;
+            assert_eq!(tag.get_type_id(), tag_type_id);
+            list.push(tag);
+        }
+        Ok(list)
+    }
+
+    fn deserialize_flat<R: Read>(reader: &mut NbtReadHelper<R>, tag_id: u8) -> Result<Self, Error> {
         match tag_id {
             END_ID => Ok(Self::End),
             BYTE_ID => {
@@ -218,22 +349,6 @@ impl NbtTag {
                 Ok(Self::ByteArray(byte_array))
             }
             STRING_ID => Ok(Self::String(get_nbt_string(reader)?)),
-            LIST_ID => {
-                let tag_type_id = reader.get_u8_be()?;
-                let len = reader.get_i32_be()?;
-                if len < 0 {
-                    return Err(Error::NegativeLength(len));
-                }
-
-                let mut list = Vec::with_capacity(len as usize);
-                for _ in 0..len {
-                    let tag = Self::deserialize_data(reader, tag_type_id)?;
-                    assert_eq!(tag.get_type_id(), tag_type_id);
-                    list.push(tag);
-                }
-                Ok(Self::List(list))
-            }
-            COMPOUND_ID => Ok(Self::Compound(NbtCompound::deserialize_content(reader)?)),
             INT_ARRAY_ID => {
                 let len = reader.get_i32_be()?;
                 if len < 0 {
@@ -241,7 +356,7 @@ impl NbtTag {
                 }
 
                 let len = len as usize;
-                let mut int_array = Vec::with_capacity(len);
+                let mut int_array = Vec::with_capacity(len.min(MAX_PREALLOCATED_LEN));
                 for _ in 0..len {
                     let int = reader.get_i32_be()?;
                     int_array.push(int);
@@ -255,7 +370,7 @@ impl NbtTag {
                 }
 
                 let len = len as usize;
-                let mut long_array = Vec::with_capacity(len);
+                let mut long_array = Vec::with_capacity(len.min(MAX_PREALLOCATED_LEN));
                 for _ in 0..len {
                     let long = reader.get_i64_be()?;
                     long_array.push(long);
@@ -369,6 +484,144 @@ impl NbtTag {
             _ => None,
         }
     }
+
+    /// Rewrites every `String` tag in this tree with `f`, including those nested in lists
+    /// and compounds. Compound keys and all other tags are left untouched.
+    #[must_use]
+    pub fn map_strings(self, f: impl Fn(String) -> String) -> Self {
+        self.map_strings_with(&f)
+    }
+
+    fn map_strings_with(self, f: &dyn Fn(String) -> String) -> Self {
+        match self {
+            Self::String(string) => Self::String(f(string)),
+            Self::List(list) => Self::List(
+                list.into_iter()
+                    .map(|tag| tag.map_strings_with(f))
+                    .collect(),
+            ),
+            Self::Compound(mut compound) => {
+                for (_, tag) in &mut compound.child_tags {
+                    *tag = std::mem::replace(tag, Self::End).map_strings_with(f);
+                }
+                Self::Compound(compound)
+            }
+            tag => tag,
+        }
+    }
+
+    /// Compares two tags by their content.
+    ///
+    /// Floating point values are equal if they are numerically equal or both NaN,
+    /// and compounds are compared regardless of the order of their keys.
+    /// This is the equality used by the `PartialEq` and `Hash` implementations.
+    #[must_use]
+    pub fn content_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::End, Self::End) => true,
+            (Self::Byte(a), Self::Byte(b)) => a == b,
+            (Self::Short(a), Self::Short(b)) => a == b,
+            (Self::Int(a), Self::Int(b)) => a == b,
+            (Self::Long(a), Self::Long(b)) => a == b,
+            (Self::Float(a), Self::Float(b)) => a == b || (a.is_nan() && b.is_nan()),
+            (Self::Double(a), Self::Double(b)) => a == b || (a.is_nan() && b.is_nan()),
+            (Self::ByteArray(a), Self::ByteArray(b)) => a == b,
+            (Self::String(a), Self::String(b)) => a == b,
+            (Self::List(a), Self::List(b)) => a == b,
+            (Self::Compound(a), Self::Compound(b)) => a.content_eq(b),
+            (Self::IntArray(a), Self::IntArray(b)) => a == b,
+            (Self::LongArray(a), Self::LongArray(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+/// Returns the length of a string written by [`NbtTag::write_string`], including its length prefix.
+fn string_len(string: &str) -> usize {
+    // Modified UTF-8 writes NUL as two bytes and characters outside the BMP as two 3 byte surrogates
+    2 + string
+        .chars()
+        .map(|c| match c {
+            '\0' => 2,
+            c if c.len_utf8() == 4 => 6,
+            c => c.len_utf8(),
+        })
+        .sum::<usize>()
+}
+
+/// Maps all NaNs to a single bit pattern and `-0.0` to `0.0`, so equal floats hash equally.
+const fn canonical_f32_bits(value: f32) -> u32 {
+    if value.is_nan() {
+        f32::NAN.to_bits()
+    } else if value == 0.0 {
+        0
+    } else {
+        value.to_bits()
+    }
+}
+
+const fn canonical_f64_bits(value: f64) -> u64 {
+    if value.is_nan() {
+        f64::NAN.to_bits()
+    } else if value == 0.0 {
+        0
+    } else {
+        value.to_bits()
+    }
+}
+
+impl PartialEq for NbtTag {
+    fn eq(&self, other: &Self) -> bool {
+        self.content_eq(other)
+    }
+}
+
+impl Eq for NbtTag {}
+
+impl Hash for NbtTag {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.get_type_id().hash(state);
+        match self {
+            Self::End => {}
+            Self::Byte(v) => v.hash(state),
+            Self::Short(v) => v.hash(state),
+            Self::Int(v) => v.hash(state),
+            Self::Long(v) => v.hash(state),
+            Self::Float(v) => canonical_f32_bits(*v).hash(state),
+            Self::Double(v) => canonical_f64_bits(*v).hash(state),
+            Self::ByteArray(v) => v.hash(state),
+            Self::String(v) => v.hash(state),
+            Self::List(v) => v.hash(state),
+            Self::Compound(v) => v.hash(state),
+            Self::IntArray(v) => v.hash(state),
+            Self::LongArray(v) => v.hash(state),
+        }
+    }
+}
+
+impl PartialOrd for NbtTag {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Self::Byte(a), Self::Byte(b)) => a.partial_cmp(b),
+            (Self::Short(a), Self::Short(b)) => a.partial_cmp(b),
+            (Self::Int(a), Self::Int(b)) => a.partial_cmp(b),
+            (Self::Long(a), Self::Long(b)) => a.partial_cmp(b),
+            // NaN equals NaN, so it has to compare as equal too
+            (Self::Float(a), Self::Float(b)) => (a.is_nan() && b.is_nan())
+                .then_some(Ordering::Equal)
+                .or_else(|| a.partial_cmp(b)),
+            (Self::Double(a), Self::Double(b)) => (a.is_nan() && b.is_nan())
+                .then_some(Ordering::Equal)
+                .or_else(|| a.partial_cmp(b)),
+            (Self::ByteArray(a), Self::ByteArray(b)) => a.partial_cmp(b),
+            (Self::String(a), Self::String(b)) => a.partial_cmp(b),
+            (Self::List(a), Self::List(b)) => a.partial_cmp(b),
+            (Self::Compound(a), Self::Compound(b)) => a.partial_cmp(b),
+            (Self::IntArray(a), Self::IntArray(b)) => a.partial_cmp(b),
+            (Self::LongArray(a), Self::LongArray(b)) => a.partial_cmp(b),
+            _ => self.get_type_id().partial_cmp(&other.get_type_id()),
+        }
+    }
 }
 
 impl From<&str> for NbtTag {
@@ -524,3 +777,300 @@ impl<'de> Deserialize<'de> for NbtTag {
         deserializer.deserialize_any(NbtTagVisitor)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::cmp::Ordering;
+    use std::collections::HashSet;
+    use std::hash::{BuildHasher, RandomState};
+    use std::io::Cursor;
+
+    use crate::compound::NbtCompound;
+    use crate::tag::NbtTag;
+    use crate::test_util::sample_compound;
+    use crate::{
+        BYTE_ARRAY_ID, COMPOUND_ID, END_ID, Error, INT_ARRAY_ID, INT_ID, LIST_ID, LONG_ARRAY_ID,
+        MAX_DEPTH,
+    };
+
+    #[test]
+    fn binary_round_trip() {
+        let tag = NbtTag::Compound(sample_compound());
+        let mut bytes = Vec::new();
+        tag.write_to(&mut bytes, "root").unwrap();
+
+        let (name, read) = NbtTag::read_from(&mut Cursor::new(bytes)).unwrap();
+        assert_eq!(name, "root");
+        assert_eq!(read, tag);
+    }
+
+    #[test]
+    fn binary_primitive_root() {
+        let mut bytes = Vec::new();
+        NbtTag::Int(0x0102_0304).write_to(&mut bytes, "a").unwrap();
+        assert_eq!(bytes, [0x03, 0x00, 0x01, b'a', 0x01, 0x02, 0x03, 0x04]);
+
+        let mut bytes = Vec::new();
+        NbtTag::End.write_to(&mut bytes, "ignored").unwrap();
+        assert_eq!(bytes, [0x00]);
+        let (name, tag) = NbtTag::read_from(&mut bytes.as_slice()).unwrap();
+        assert_eq!((name.as_str(), tag), ("", NbtTag::End));
+    }
+
+    #[test]
+    fn network_round_trip() {
+        let tag = NbtTag::Compound(sample_compound());
+        let mut named = Vec::new();
+        tag.write_to(&mut named, "").unwrap();
+        let mut network = Vec::new();
+        tag.write_network(&mut network).unwrap();
+
+        assert_eq!(network.len(), named.len() - 2);
+        assert_eq!(NbtTag::read_network(&mut network.as_slice()).unwrap(), tag);
+
+        let mut network = Vec::new();
+        NbtTag::End.write_network(&mut network).unwrap();
+        assert_eq!(
+            NbtTag::read_network(&mut network.as_slice()).unwrap(),
+            NbtTag::End
+        );
+    }
+
+    #[test]
+    fn network_rejects_non_compound_root() {
+        let mut bytes = Vec::new();
+        assert!(matches!(
+            NbtTag::Int(1).write_network(&mut bytes),
+            Err(Error::NoRootCompound(INT_ID))
+        ));
+        assert_eq!(bytes.len(), 0);
+
+        let bytes = [INT_ID, 0, 0, 0, 1];
+        assert!(matches!(
+            NbtTag::read_network(&mut bytes.as_slice()),
+            Err(Error::NoRootCompound(INT_ID))
+        ));
+    }
+
+    /// Network bytes for `levels` compounds nested inside the root compound.
+    fn nested_compounds(levels: usize) -> Vec<u8> {
+        let mut bytes = vec![COMPOUND_ID];
+        for _ in 0..levels {
+            bytes.extend([COMPOUND_ID, 0, 0]);
+        }
+        bytes.resize(bytes.len() + levels + 1, END_ID);
+        bytes
+    }
+
+    #[test]
+    fn network_limits_nesting_depth() {
+        let bytes = nested_compounds(MAX_DEPTH - 1);
+        assert!(NbtTag::read_network(&mut bytes.as_slice()).is_ok());
+
+        let bytes = nested_compounds(MAX_DEPTH);
+        assert!(matches!(
+            NbtTag::read_network(&mut bytes.as_slice()),
+            Err(Error::TooDeep(MAX_DEPTH))
+        ));
+
+        // Big enough to overflow the stack without the limit
+        let bytes = nested_compounds(1_000_000);
+        assert!(matches!(
+            NbtTag::read_network(&mut bytes.as_slice()),
+            Err(Error::TooDeep(MAX_DEPTH))
+        ));
+    }
+
+    #[test]
+    fn network_does_not_trust_lengths() {
+        // A list claiming i32::MAX ints, followed by nothing
+        let bytes = [COMPOUND_ID, LIST_ID, 0, 0, INT_ID, 0x7f, 0xff, 0xff, 0xff];
+        assert!(matches!(
+            NbtTag::read_network(&mut bytes.as_slice()),
+            Err(Error::Incomplete(_))
+        ));
+
+        for array_id in [BYTE_ARRAY_ID, INT_ARRAY_ID, LONG_ARRAY_ID] {
+            let bytes = [COMPOUND_ID, array_id, 0, 0, 0x7f, 0xff, 0xff, 0xff];
+            assert!(matches!(
+                NbtTag::read_network(&mut bytes.as_slice()),
+                Err(Error::Incomplete(_))
+            ));
+        }
+
+        let bytes = [COMPOUND_ID, LIST_ID, 0, 0, END_ID, 0x7f, 0xff, 0xff, 0xff];
+        assert!(matches!(
+            NbtTag::read_network(&mut bytes.as_slice()),
+            Err(Error::NonEmptyEndList(i32::MAX))
+        ));
+    }
+
+    #[test]
+    fn network_len_matches_written_len() {
+        let mut strings = NbtCompound::new();
+        strings.put_string("", String::new());
+        strings.put_string("\u{e9}\u{20ac}", "\0\u{1F383}".to_string());
+
+        let mut lists = NbtCompound::new();
+        lists.put_list("empty", vec![]);
+        lists.put_list("shorts", vec![NbtTag::Short(1), NbtTag::Short(2)]);
+        lists.put_list(
+            "nested",
+            vec![NbtTag::List(vec![NbtTag::Compound(strings.clone())])],
+        );
+
+        for tag in [
+            NbtTag::End,
+            NbtTag::Compound(NbtCompound::new()),
+            NbtTag::Compound(strings),
+            NbtTag::Compound(lists),
+            NbtTag::Compound(sample_compound()),
+        ] {
+            let mut bytes = Vec::new();
+            tag.write_network(&mut bytes).unwrap();
+            assert_eq!(tag.network_len(), Some(bytes.len()), "{tag}");
+        }
+    }
+
+    #[test]
+    fn network_len_rejects_non_compound_root() {
+        assert_eq!(NbtTag::Int(1).network_len(), None);
+        assert_eq!(NbtTag::List(vec![]).network_len(), None);
+    }
+
+    #[test]
+    fn map_strings_rewrites_values_only() {
+        let mut block = NbtCompound::new();
+        block.put_string("Name", "stone".to_string());
+        block.put_int("count", 2);
+        let mut root = NbtCompound::new();
+        root.put_string("id", "chest".to_string());
+        root.put_list("blocks", vec![NbtTag::Compound(block)]);
+        root.put_list("tags", vec![NbtTag::String("dirt".to_string())]);
+        root.put("data", NbtTag::IntArray(vec![7]));
+
+        let mapped = NbtTag::Compound(root).map_strings(|s| format!("minecraft:{s}"));
+        assert_eq!(
+            mapped.to_snbt(),
+            r#"{id:"minecraft:chest",blocks:[{Name:"minecraft:stone",count:2}],tags:["minecraft:dirt"],data:[I;7]}"#
+        );
+    }
+
+    #[test]
+    fn binary_truncated() {
+        let mut bytes = Vec::new();
+        NbtTag::Compound(sample_compound())
+            .write_to(&mut bytes, "root")
+            .unwrap();
+        bytes.truncate(10);
+        assert!(NbtTag::read_from(&mut bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn binary_missing_end_tag() {
+        let mut compound = NbtCompound::new();
+        compound.put_int("a", 1);
+        compound.put_int("b", 2);
+        let tag = NbtTag::Compound(compound);
+
+        let mut bytes = Vec::new();
+        tag.write_to(&mut bytes, "r").unwrap();
+        bytes.pop();
+        assert!(matches!(
+            NbtTag::read_from(&mut bytes.as_slice()),
+            Err(Error::Incomplete(_))
+        ));
+
+        let mut bytes = Vec::new();
+        tag.write_network(&mut bytes).unwrap();
+        bytes.pop();
+        assert!(matches!(
+            NbtTag::read_network(&mut bytes.as_slice()),
+            Err(Error::Incomplete(_))
+        ));
+    }
+
+    #[test]
+    fn binary_limits_depth_and_lengths() {
+        // A named root compound wrapping the payloads that break `read_network`
+        let named = |payload: &[u8]| [&[COMPOUND_ID, 0, 1, b'r'], &payload[1..]].concat();
+
+        let bytes = named(&nested_compounds(1_000_000));
+        assert!(matches!(
+            NbtTag::read_from(&mut bytes.as_slice()),
+            Err(Error::TooDeep(MAX_DEPTH))
+        ));
+
+        let bytes = named(&[COMPOUND_ID, LIST_ID, 0, 0, INT_ID, 0x7f, 0xff, 0xff, 0xff]);
+        assert!(matches!(
+            NbtTag::read_from(&mut bytes.as_slice()),
+            Err(Error::Incomplete(_))
+        ));
+    }
+
+    #[test]
+    fn content_eq_compounds_hash_equally() {
+        let mut first = NbtCompound::new();
+        first.put_int("a", 1);
+        first.put_double("nan", f64::NAN);
+        first.put_string("b", "two".to_string());
+
+        let mut second = NbtCompound::new();
+        second.put_string("b", "two".to_string());
+        second.put_double("nan", -f64::NAN);
+        second.put_int("a", 1);
+
+        let first = NbtTag::Compound(first);
+        let second = NbtTag::Compound(second);
+        assert!(first.content_eq(&second));
+        assert_eq!(first, second);
+
+        let state = RandomState::new();
+        assert_eq!(state.hash_one(&first), state.hash_one(&second));
+
+        let set: HashSet<NbtTag> = [first, second].into_iter().collect();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn signed_zero_hashes_equally() {
+        let state = RandomState::new();
+        assert_eq!(NbtTag::Float(0.0), NbtTag::Float(-0.0));
+        assert_eq!(
+            state.hash_one(NbtTag::Float(0.0)),
+            state.hash_one(NbtTag::Float(-0.0))
+        );
+    }
+
+    #[test]
+    fn ordering_agrees_with_equality() {
+        let nan = NbtTag::List(vec![NbtTag::Double(f64::NAN)]);
+        assert_eq!(nan.partial_cmp(&nan.clone()), Some(Ordering::Equal));
+        assert_eq!(
+            NbtTag::Float(0.0).partial_cmp(&NbtTag::Float(-0.0)),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            NbtTag::Float(f32::NAN).partial_cmp(&NbtTag::Float(1.0)),
+            None
+        );
+        assert!(NbtTag::Int(2) < NbtTag::Long(1));
+
+        let mut compound = NbtCompound::new();
+        compound.put_float("nan", f32::NAN);
+        compound.put_int("a", 1);
+        let tag = NbtTag::Compound(compound);
+        assert_eq!(tag.partial_cmp(&tag.clone()), Some(Ordering::Equal));
+    }
+
+    #[test]
+    fn different_tags_are_not_equal() {
+        assert_ne!(NbtTag::Int(1), NbtTag::Long(1));
+        assert_ne!(NbtTag::Float(1.0), NbtTag::Float(f32::NAN));
+
+        let set: HashSet<NbtTag> = [NbtTag::Int(1), NbtTag::Long(1), NbtTag::Int(1)]
+            .into_iter()
+            .collect();
+        assert_eq!(set.len(), 2);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::compound::NbtCompound;
+use crate::tag::NbtTag;
+
+/// Builds the compound shared by the tests of this crate.
+///
+/// It holds every tag type, strings that need modified UTF-8 and SNBT quoting,
+/// and a list of compounds for lookups by path:
+///
+/// ```text
+/// {byte:-8b,short:16s,int:32,long:64L,float:1.5f,double:-2.25d,
+///  text:"nul\0 and emoji 🎃",quoted:'say "it\'s"',
+///  items:[{id:"minecraft:dirt"},{id:"minecraft:stone",count:3b,bytes:[B;1B,2B,-1B],
+///         ints:[I;-1,0,2147483647],longs:[L;-9223372036854775808L]}],
+///  empty:[],"inner key":{list:["a","b"]}}
+/// ```
+pub fn sample_compound() -> NbtCompound {
+    let mut dirt = NbtCompound::new();
+    dirt.put_string("id", "minecraft:dirt".to_string());
+
+    let mut stone = NbtCompound::new();
+    stone.put_string("id", "minecraft:stone".to_string());
+    stone.put_byte("count", 3);
+    stone.put(
+        "bytes",
+        NbtTag::ByteArray(vec![1, 2, 255].into_boxed_slice()),
+    );
+    stone.put("ints", NbtTag::IntArray(vec![-1, 0, i32::MAX]));
+    stone.put("longs", NbtTag::LongArray(vec![i64::MIN]));
+
+    let mut inner = NbtCompound::new();
+    inner.put_list(
+        "list",
+        vec![
+            NbtTag::String("a".to_string()),
+            NbtTag::String("b".to_string()),
+        ],
+    );
+
+    let mut compound = NbtCompound::new();
+    compound.put_byte("byte", -8);
+    compound.put_short("short", 16);
+    compound.put_int("int", 32);
+    compound.put_long("long", 64);
+    compound.put_float("float", 1.5);
+    compound.put_double("double", -2.25);
+    compound.put_string("text", "nul\0 and emoji \u{1F383}".to_string());
+    compound.put_string("quoted", r#"say "it's""#.to_string());
+    compound.put_list(
+        "items",
+        vec![NbtTag::Compound(dirt), NbtTag::Compound(stone)],
+    );
+    compound.put_list("empty", vec![]);
+    compound.put_component("inner key", inner);
+    compound
+}
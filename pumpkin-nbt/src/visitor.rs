@@ -0,0 +1,112 @@
+use crate::compound::NbtCompound;
+use crate::tag::NbtTag;
+
+/// Inspects the nodes of a tag tree walked by [`NbtTag::accept`].
+///
+/// Every method does nothing by default, so visitors only override what they need.
+pub trait NbtVisitor {
+    /// Called for a compound before any of its values are visited.
+    fn visit_compound(&mut self, _compound: &NbtCompound) {}
+
+    /// Called for a list before any of its elements are visited.
+    fn visit_list(&mut self, _list: &[NbtTag]) {}
+
+    fn visit_string(&mut self, _string: &str) {}
+
+    /// Called for `Byte`, `Short`, `Int`, `Long`, `Float` and `Double` tags.
+    fn visit_number(&mut self, _number: &NbtTag) {}
+
+    /// Called for `ByteArray`, `IntArray` and `LongArray` tags.
+    fn visit_array(&mut self, _array: &NbtTag) {}
+}
+
+impl NbtTag {
+    /// Walks this tag and all of its children depth-first, calling `visitor` for each of them.
+    pub fn accept(&self, visitor: &mut impl NbtVisitor) {
+        match self {
+            Self::End => {}
+            Self::Byte(_)
+            | Self::Short(_)
+            | Self::Int(_)
+            | Self::Long(_)
+            | Self::Float(_)
+            | Self::Double(_) => visitor.visit_number(self),
+            Self::ByteArray(_) | Self::IntArray(_) | Self::LongArray(_) => {
+                visitor.visit_array(self);
+            }
+            Self::String(string) => visitor.visit_string(string),
+            Self::List(list) => {
+                visitor.visit_list(list);
+                for tag in list {
+                    tag.accept(visitor);
+                }
+            }
+            Self::Compound(compound) => {
+                visitor.visit_compound(compound);
+                for (_, tag) in &compound.child_tags {
+                    tag.accept(visitor);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::compound::NbtCompound;
+    use crate::tag::NbtTag;
+    use crate::test_util::sample_compound;
+    use crate::visitor::NbtVisitor;
+
+    #[derive(Default)]
+    struct Counter {
+        strings: Vec<String>,
+        numbers: usize,
+        containers: usize,
+    }
+
+    impl NbtVisitor for Counter {
+        fn visit_compound(&mut self, _compound: &NbtCompound) {
+            self.containers += 1;
+        }
+
+        fn visit_list(&mut self, _list: &[NbtTag]) {
+            self.containers += 1;
+        }
+
+        fn visit_string(&mut self, string: &str) {
+            self.strings.push(string.to_string());
+        }
+
+        fn visit_number(&mut self, _number: &NbtTag) {
+            self.numbers += 1;
+        }
+    }
+
+    struct Nothing;
+
+    impl NbtVisitor for Nothing {}
+
+    #[test]
+    fn count_strings() {
+        let root = NbtTag::Compound(sample_compound());
+
+        let mut counter = Counter::default();
+        root.accept(&mut counter);
+        assert_eq!(
+            counter.strings,
+            [
+                "nul\0 and emoji \u{1F383}",
+                r#"say "it's""#,
+                "minecraft:dirt",
+                "minecraft:stone",
+                "a",
+                "b"
+            ]
+        );
+        assert_eq!(counter.numbers, 7);
+        assert_eq!(counter.containers, 7);
+
+        root.accept(&mut Nothing);
+    }
+}